@@ -4,11 +4,62 @@ use log::info;
 use crate::external::gtfs::agency::Agency;
 use crate::external::gtfs::calendar::Calendar;
 use crate::external::gtfs::calendar_dates::CalendarDate;
+use crate::external::gtfs::fare_attributes::FareAttribute;
+use crate::external::gtfs::fare_rules::FareRule;
+use crate::external::gtfs::frequencies::Frequency;
 use crate::external::gtfs::routes::Route;
+use crate::external::gtfs::shapes::Shape;
+use crate::external::gtfs::stop_times::StopTime;
 use crate::external::gtfs::stops::Stop;
+use crate::external::gtfs::transfers::Transfer;
 use crate::external::gtfs::trips::Trip;
+use crate::external::gtfscsv::GTFSFile;
+use crate::external::gtfsdb::Table;
 use crate::{external, io};
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::Path;
+
+/// ディレクトリから一度だけ読みこんだGTFS全テーブル
+///
+/// validateとinsertで同じ(巨大になりうる)stop_timesを二重に読まないよう、
+/// パース済みのデータを両者で共有するために用いる。
+pub struct GtfsFeed {
+    pub agencies: Vec<Agency>,
+    pub stops: Vec<Stop>,
+    pub routes: Vec<Route>,
+    pub trips: Vec<Trip>,
+    pub stop_times: Vec<StopTime>,
+    pub calendars: Vec<Calendar>,
+    pub calendar_dates: Vec<CalendarDate>,
+    pub shapes: Vec<Shape>,
+    pub frequencies: Vec<Frequency>,
+    pub transfers: Vec<Transfer>,
+    pub fare_attributes: Vec<FareAttribute>,
+    pub fare_rules: Vec<FareRule>,
+}
+
+impl GtfsFeed {
+    /// GTFSディレクトリから全テーブルを読みこむ
+    ///
+    /// calendar_datesと各fare/shape/frequency/transferは任意項目なので、
+    /// 欠落していても失敗させず空として扱う。
+    pub fn load(gtfs_dir: &Path) -> Result<Self> {
+        Ok(Self {
+            agencies: io::read(&gtfs_dir.join("agency.txt"))?,
+            stops: io::read(&gtfs_dir.join("stops.txt"))?,
+            routes: io::read(&gtfs_dir.join("routes.txt"))?,
+            trips: io::read(&gtfs_dir.join("trips.txt"))?,
+            stop_times: io::read(&gtfs_dir.join("stop_times.txt"))?,
+            calendars: io::read(&gtfs_dir.join("calendar.txt"))?,
+            calendar_dates: io::read_optional(&gtfs_dir.join("calendar_dates.txt"))?,
+            shapes: io::read_optional(&gtfs_dir.join("shapes.txt"))?,
+            frequencies: io::read_optional(&gtfs_dir.join("frequencies.txt"))?,
+            transfers: io::read_optional(&gtfs_dir.join("transfers.txt"))?,
+            fare_attributes: io::read_optional(&gtfs_dir.join("fare_attributes.txt"))?,
+            fare_rules: io::read_optional(&gtfs_dir.join("fare_rules.txt"))?,
+        })
+    }
+}
 
 pub struct GtfsService {
     gtfs: Box<dyn external::gtfs::Gtfs>,
@@ -27,42 +78,113 @@ impl GtfsService {
         Ok(())
     }
 
-    pub fn insert_tables(&mut self, gtfs_dir: &PathBuf) -> Result<()> {
-        let agencies = io::read::<Agency>(&gtfs_dir.join("agency.txt"))?;
-        info!("ℹ️ [agencies] {} records", agencies.len());
-        self.gtfs.insert_agencies(&agencies)?;
+    pub fn insert_tables(&mut self, feed: &GtfsFeed) -> Result<()> {
+        info!("ℹ️ [agencies] {} records", feed.agencies.len());
+        self.gtfs.insert_agencies(&feed.agencies)?;
+        info!("  ✨ Success");
+
+        info!("ℹ️ [stops] {} records", feed.stops.len());
+        self.gtfs.insert_stops(&feed.stops)?;
+        info!("  ✨ Success");
+
+        info!("ℹ️ [routes] {} records", feed.routes.len());
+        self.gtfs.insert_routes(&feed.routes)?;
+        info!("  ✨ Success");
+
+        info!("ℹ️ [trips] {} records", feed.trips.len());
+        self.gtfs.insert_trips(&feed.trips)?;
+        info!("  ✨ Success");
+
+        info!("ℹ️ [stop_times] {} records", feed.stop_times.len());
+        self.gtfs.insert_stop_times(&feed.stop_times)?;
+        info!("  ✨ Success");
+
+        info!("ℹ️ [calendar] {} records", feed.calendars.len());
+        self.gtfs.insert_calendars(&feed.calendars)?;
+        info!("  ✨ Success");
+
+        info!("ℹ️ [calendar_dates] {} records", feed.calendar_dates.len());
+        self.gtfs.insert_calendar_dates(&feed.calendar_dates)?;
         info!("  ✨ Success");
 
-        let stops = io::read::<Stop>(&gtfs_dir.join("stops.txt"))?;
-        info!("ℹ️ [stops] {} records", stops.len());
-        self.gtfs.insert_stops(&stops)?;
+        info!("ℹ️ [shapes] {} records", feed.shapes.len());
+        self.gtfs.insert_shapes(&feed.shapes)?;
         info!("  ✨ Success");
 
-        let routes = io::read::<Route>(&gtfs_dir.join("routes.txt"))?;
-        info!("ℹ️ [routes] {} records", routes.len());
-        self.gtfs.insert_routes(&routes)?;
+        info!("ℹ️ [frequencies] {} records", feed.frequencies.len());
+        self.gtfs.insert_frequencies(&feed.frequencies)?;
         info!("  ✨ Success");
 
-        let trips = io::read::<Trip>(&gtfs_dir.join("trips.txt"))?;
-        info!("ℹ️ [trips] {} records", trips.len());
-        self.gtfs.insert_trips(&trips)?;
+        info!("ℹ️ [transfers] {} records", feed.transfers.len());
+        self.gtfs.insert_transfers(&feed.transfers)?;
         info!("  ✨ Success");
 
-        // let stop_times = io::read::<StopTime>(&gtfs_dir.join("stop_times.txt"))?;
-        // info!("ℹ️ [stop_times] {} records", stop_times.len());
-        // self.gtfs.insert_stop_times(&stop_times)?;
-        // info!("  ✨ Success");
+        info!("ℹ️ [fare_attributes] {} records", feed.fare_attributes.len());
+        self.gtfs.insert_fare_attributes(&feed.fare_attributes)?;
+        info!("  ✨ Success");
 
-        let calendars = io::read::<Calendar>(&gtfs_dir.join("calendar.txt"))?;
-        info!("ℹ️ [calendar] {} records", calendars.len());
-        self.gtfs.insert_calendars(&calendars)?;
+        info!("ℹ️ [fare_rules] {} records", feed.fare_rules.len());
+        self.gtfs.insert_fare_rules(&feed.fare_rules)?;
         info!("  ✨ Success");
 
-        let calendar_dates = io::read::<CalendarDate>(&gtfs_dir.join("calendar_dates.txt"))?;
-        info!("ℹ️ [calendar_dates] {} records", calendar_dates.len());
-        self.gtfs.insert_calendar_dates(&calendar_dates)?;
+        Ok(())
+    }
+
+    /// データベースの全テーブルをGTFSフィード(ディレクトリ)へ書き出す
+    ///
+    /// 各テーブルは`GTFSFile::file_name()`へRFC-4180のCSVとして出力し、
+    /// ヘッダは`Table::column_names()`の順序に合わせる。空のテーブルは
+    /// ファイルごと出力しない。
+    pub fn export_tables(&self, out_dir: &Path) -> Result<()> {
+        info!("ℹ️ Export all tables to {}.", out_dir.display());
+        std::fs::create_dir_all(out_dir)?;
+
+        write_table(&self.gtfs.select_agencies()?, out_dir)?;
+        write_table(&self.gtfs.select_stops()?, out_dir)?;
+        write_table(&self.gtfs.select_routes()?, out_dir)?;
+        write_table(&self.gtfs.select_trips()?, out_dir)?;
+        write_table(&self.gtfs.select_stop_times()?, out_dir)?;
+        write_table(&self.gtfs.select_calendars()?, out_dir)?;
+        write_table(&self.gtfs.select_calendar_dates()?, out_dir)?;
+        write_table(&self.gtfs.select_shapes()?, out_dir)?;
+        write_table(&self.gtfs.select_frequencies()?, out_dir)?;
+        write_table(&self.gtfs.select_transfers()?, out_dir)?;
+        write_table(&self.gtfs.select_fare_attributes()?, out_dir)?;
+        write_table(&self.gtfs.select_fare_rules()?, out_dir)?;
+
         info!("  ✨ Success");
+        Ok(())
+    }
+
+    /// 挿入前にGTFSフィードの参照整合性を検証し、違反の一覧を返す
+    ///
+    /// パース済みの[`GtfsFeed`]を受け取り、insertと同じデータを使いまわす。
+    pub fn validate_tables(&self, feed: &GtfsFeed) -> Vec<crate::validate::Violation> {
+        info!("ℹ️ Validate referential integrity.");
+        let violations = crate::validate::validate(
+            &feed.agencies,
+            &feed.stops,
+            &feed.routes,
+            &feed.trips,
+            &feed.stop_times,
+            &feed.calendars,
+            &feed.calendar_dates,
+        );
+        info!("  ℹ️ {} violation(s)", violations.len());
+        violations
+    }
 
+    /// 停留所・経路・事業者の名称を全文検索インデックスへ登録する
+    pub fn build_search_index(&self, database: &Path) -> Result<()> {
+        info!("ℹ️ Build search index.");
+        let dir = crate::search::SearchIndex::dir_for(database);
+        let index = crate::search::SearchIndex::create(&dir)?;
+        index.index_entities(
+            &self.gtfs.select_agencies()?,
+            &self.gtfs.select_stops()?,
+            &self.gtfs.select_routes()?,
+        )?;
+        info!("  ✨ Success");
         Ok(())
     }
 
@@ -73,3 +195,27 @@ impl GtfsService {
         Ok(())
     }
 }
+
+/// 1テーブルを`GTFSFile::file_name()`へCSVとして書き出す
+///
+/// ヘッダは`Table::column_names()`の順序で明示的に出力し、空のテーブルは
+/// (参照実装のtransfersと同様に)ファイルごとスキップする。
+fn write_table<T>(records: &[T], out_dir: &Path) -> Result<()>
+where
+    T: GTFSFile + Table + Serialize,
+{
+    if records.is_empty() {
+        return Ok(());
+    }
+    let path = out_dir.join(T::file_name());
+    info!("ℹ️ [{}] {} records", T::table_name(), records.len());
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(&path)?;
+    wtr.write_record(T::column_names())?;
+    for record in records {
+        wtr.serialize(record)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -5,7 +5,10 @@ use crate::cmd;
 
 pub mod convert;
 pub mod create;
+pub mod export;
 pub mod get;
+pub mod search;
+pub mod validate;
 
 #[derive(Clap, Debug)]
 pub struct Opts {
@@ -21,6 +24,12 @@ pub enum SubCommand {
     Get(cmd::db::get::Opts),
     /// データベースからデータを変換する
     Convert(cmd::db::convert::Opts),
+    /// データベースをGTFSフィードとして書き出す
+    Export(cmd::db::export::Opts),
+    /// 停留所・経路・事業者を全文検索する
+    Search(cmd::db::search::Opts),
+    /// GTFSフィードの参照整合性を検証する
+    Validate(cmd::db::validate::Opts),
 }
 
 pub fn run(opts: &Opts) -> Result<()> {
@@ -28,5 +37,8 @@ pub fn run(opts: &Opts) -> Result<()> {
         SubCommand::Create(op) => cmd::db::create::run(op),
         SubCommand::Get(op) => cmd::db::get::run(op),
         SubCommand::Convert(op) => cmd::db::convert::run(op),
+        SubCommand::Export(op) => cmd::db::export::run(op),
+        SubCommand::Search(op) => cmd::db::search::run(op),
+        SubCommand::Validate(op) => cmd::db::validate::run(op),
     }
 }
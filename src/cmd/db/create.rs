@@ -4,13 +4,13 @@ use anyhow::Result;
 use clap::Clap;
 use strum::VariantNames;
 
-use crate::app::gtfs::GtfsService;
-use crate::external;
+use crate::app::gtfs::{GtfsFeed, GtfsService};
 use crate::external::gtfs::extended::service_routes;
+use crate::{external, io};
 
 #[derive(Clap, Debug)]
 pub struct Opts {
-    /// 読みこむGTFSが配置されたディレクトリのパス
+    /// 読みこむGTFS (ディレクトリ・zipアーカイブ・http(s) URLのいずれか)
     #[clap(parse(from_os_str))]
     pub gtfs_dir: PathBuf,
     /// 作成するデータベースファイルのパス
@@ -30,17 +30,37 @@ pub struct Opts {
     /// service_route識別ファイルのパス
     #[clap(short = 's', long, parse(from_os_str))]
     pub service_route_identify: Option<PathBuf>,
+    /// 参照整合性違反があれば挿入せずに中断する
+    #[clap(long)]
+    pub strict: bool,
 }
 
 pub fn run(op: &Opts) -> Result<()> {
-    let gtfs_csv = external::gtfscsv::GtfsCsv::new(&op.gtfs_dir)?;
-    let gtfs_db = external::gtfsdb::init(&op.database)?;
+    // zip/URLの入力は一時ディレクトリへ展開し、その実体ディレクトリから読む
+    let source = external::gtfscsv::Source::from_arg(&op.gtfs_dir);
+    let gtfs_csv = external::gtfscsv::GtfsCsv::new(&source)?;
+    let gtfs_dir = gtfs_csv.dir();
 
-    let mut service = GtfsService::new(gtfs_csv, gtfs_db);
+    // validateとinsertで同じデータを使いまわし、stop_timesを二重に読まない
+    let feed = GtfsFeed::load(gtfs_dir)?;
+
+    let gtfs = external::gtfsdb::GtfsDb::new(&op.database)?;
+    let mut service = GtfsService::new(Box::new(gtfs));
+
+    let violations = service.validate_tables(&feed);
+    if !violations.is_empty() {
+        io::write(&violations, &io::Format::Csv)?;
+        if op.strict {
+            anyhow::bail!(
+                "{} referential-integrity violation(s); aborting (--strict)",
+                violations.len()
+            );
+        }
+    }
 
     service.drop_tables()?;
     service.create_tables()?;
-    service.insert_tables(op.legacy_translations)?;
+    service.insert_tables(&feed)?;
 
     service.insert_service_routes_tables(
         &op.service_route_identify_strategy,
@@ -48,5 +68,7 @@ pub fn run(op: &Opts) -> Result<()> {
     )?;
     service.insert_nodes_tables()?;
 
+    service.build_search_index(&op.database)?;
+
     Ok(())
 }
@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Clap;
+
+use crate::app::gtfs::GtfsService;
+use crate::external;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    /// 読み込むデータベースファイルのパス
+    #[clap(short, long, parse(from_os_str), default_value = "gtfs.db")]
+    pub database: PathBuf,
+    /// 書き出し先ディレクトリのパス
+    #[clap(short, long, parse(from_os_str), default_value = "gtfs_out")]
+    pub out: PathBuf,
+    /// 書き出したGTFSをzipアーカイブにまとめる場合のパス
+    #[clap(short, long, parse(from_os_str))]
+    pub zip: Option<PathBuf>,
+}
+
+pub fn run(op: &Opts) -> Result<()> {
+    let gtfs = external::gtfsdb::GtfsDb::new(&op.database)?;
+    let service = GtfsService::new(Box::new(gtfs));
+
+    service.export_tables(&op.out)?;
+
+    if let Some(zip_path) = &op.zip {
+        package_zip(&op.out, zip_path)?;
+    }
+
+    Ok(())
+}
+
+/// 書き出し済みのディレクトリをzipアーカイブへまとめる
+fn package_zip(dir: &std::path::Path, zip_path: &std::path::Path) -> Result<()> {
+    let file = File::create(zip_path)?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        writer.start_file(name, options)?;
+        let mut buf = Vec::new();
+        File::open(&path)?.read_to_end(&mut buf)?;
+        writer.write_all(&buf)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
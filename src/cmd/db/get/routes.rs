@@ -5,6 +5,8 @@ use clap::Clap;
 use strum::VariantNames;
 
 use crate::app::route::{RouteService, RouteServiceDb};
+use crate::external::gtfs::Gtfs;
+use crate::ics::{self, RouteCalendar};
 use crate::io::Format;
 use crate::{external, io};
 
@@ -20,6 +22,34 @@ pub struct Opts {
 
 pub fn run(op: &Opts) -> Result<()> {
     let gtfs = external::gtfsdb::GtfsDb::new(&op.database)?;
+
+    // icsは路線のダイヤ全体を束ねるため、汎用のio::writeではなく専用経路で出力する
+    if let Format::Ics = op.format {
+        let routes = gtfs.select_routes()?;
+        let trips = gtfs.select_trips()?;
+        let stop_times = gtfs.select_stop_times()?;
+        let stops = gtfs.select_stops()?;
+        let calendars = gtfs.select_calendars()?;
+        let calendar_dates = gtfs.select_calendar_dates()?;
+        // 全路線を1つのVCALENDARへ束ねる(複数VCALENDARだと先頭しか取りこまない
+        // クライアントが多いため)
+        let route_calendars: Vec<RouteCalendar> = routes
+            .iter()
+            .map(|route| {
+                RouteCalendar::new(
+                    route,
+                    &trips,
+                    &stop_times,
+                    &stops,
+                    &calendars,
+                    &calendar_dates,
+                )
+            })
+            .collect();
+        print!("{}", ics::to_combined_ics(&route_calendars)?);
+        return Ok(());
+    }
+
     let routes = RouteServiceDb::new(gtfs).fetch()?;
     io::write(&routes, &op.format)?;
     Ok(())
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Clap;
+use strum::VariantNames;
+
+use crate::io;
+use crate::io::Format;
+use crate::search::SearchIndex;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    /// 検索クエリ (ex: 渋谷)
+    pub query: String,
+    /// 読み込むデータベースファイルのパス
+    #[clap(short, long, parse(from_os_str), default_value = "gtfs.db")]
+    pub database: PathBuf,
+    /// 取得する最大件数
+    #[clap(short, long, default_value = "20")]
+    pub limit: usize,
+    /// 出力フォーマット
+    #[clap(short, long, default_value = "csv", possible_values(Format::VARIANTS))]
+    pub format: Format,
+}
+
+pub fn run(op: &Opts) -> Result<()> {
+    let dir = SearchIndex::dir_for(&op.database);
+    let index = SearchIndex::open(&dir)?;
+    let hits = index.query(&op.query, op.limit)?;
+    io::write(&hits, &op.format)?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Clap;
+use strum::VariantNames;
+
+use crate::app::gtfs::{GtfsFeed, GtfsService};
+use crate::io::Format;
+use crate::{external, io};
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    /// 検証するGTFSが配置されたディレクトリのパス
+    #[clap(parse(from_os_str))]
+    pub gtfs_dir: PathBuf,
+    /// 作業用データベースファイルのパス
+    #[clap(short, long, parse(from_os_str), default_value = "gtfs.db")]
+    pub database: PathBuf,
+    /// 出力フォーマット
+    #[clap(short, long, default_value = "csv", possible_values(Format::VARIANTS))]
+    pub format: Format,
+}
+
+pub fn run(op: &Opts) -> Result<()> {
+    let gtfs = external::gtfsdb::GtfsDb::new(&op.database)?;
+    let service = GtfsService::new(Box::new(gtfs));
+
+    let feed = GtfsFeed::load(&op.gtfs_dir)?;
+    let violations = service.validate_tables(&feed);
+    io::write(&violations, &op.format)?;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+
+use crate::external::gtfs::agency::Agency;
+use crate::external::gtfs::calendar::Calendar;
+use crate::external::gtfs::calendar_dates::CalendarDate;
+use crate::external::gtfs::fare_attributes::FareAttribute;
+use crate::external::gtfs::fare_rules::FareRule;
+use crate::external::gtfs::frequencies::Frequency;
+use crate::external::gtfs::routes::Route;
+use crate::external::gtfs::shapes::Shape;
+use crate::external::gtfs::stop_times::StopTime;
+use crate::external::gtfs::stops::Stop;
+use crate::external::gtfs::transfers::Transfer;
+use crate::external::gtfs::trips::Trip;
+
+pub mod agency;
+pub mod calendar;
+pub mod calendar_dates;
+pub mod fare_attributes;
+pub mod fare_rules;
+pub mod frequencies;
+pub mod routes;
+pub mod shapes;
+pub mod stop_times;
+pub mod stops;
+pub mod transfers;
+pub mod trips;
+
+/// 言語 (ex: ja)
+pub type Lang = String;
+/// メールアドレス
+pub type MailAddress = String;
+/// 電話番号
+pub type TelephoneNumber = String;
+/// タイムゾーン (ex: Asia/Tokyo)
+pub type Timezone = String;
+/// URL
+pub type Url = String;
+/// 通貨 (ex: JPY)
+pub type Currency = String;
+
+/// GTFSの永続化層を抽象化するトレイト
+pub trait Gtfs {
+    fn create_all(&self) -> Result<()>;
+    fn drop_all(&self) -> Result<()>;
+
+    fn insert_agencies(&mut self, agencies: &[Agency]) -> Result<()>;
+    fn insert_stops(&mut self, stops: &[Stop]) -> Result<()>;
+    fn insert_routes(&mut self, routes: &[Route]) -> Result<()>;
+    fn insert_trips(&mut self, trips: &[Trip]) -> Result<()>;
+    /// stop_timesは数百万行になりうるため、実装はトランザクション内で
+    /// チャンク単位のバルクインサートを行うこと。
+    fn insert_stop_times(&mut self, stop_times: &[StopTime]) -> Result<()>;
+    fn insert_calendars(&mut self, calendars: &[Calendar]) -> Result<()>;
+    fn insert_calendar_dates(&mut self, calendar_dates: &[CalendarDate]) -> Result<()>;
+    fn insert_shapes(&mut self, shapes: &[Shape]) -> Result<()>;
+    fn insert_frequencies(&mut self, frequencies: &[Frequency]) -> Result<()>;
+    fn insert_transfers(&mut self, transfers: &[Transfer]) -> Result<()>;
+    fn insert_fare_attributes(&mut self, fare_attributes: &[FareAttribute]) -> Result<()>;
+    fn insert_fare_rules(&mut self, fare_rules: &[FareRule]) -> Result<()>;
+
+    fn select_agencies(&self) -> Result<Vec<Agency>>;
+    fn select_stops(&self) -> Result<Vec<Stop>>;
+    fn select_routes(&self) -> Result<Vec<Route>>;
+    fn select_trips(&self) -> Result<Vec<Trip>>;
+    fn select_stop_times(&self) -> Result<Vec<StopTime>>;
+    fn select_calendars(&self) -> Result<Vec<Calendar>>;
+    fn select_calendar_dates(&self) -> Result<Vec<CalendarDate>>;
+    fn select_shapes(&self) -> Result<Vec<Shape>>;
+    fn select_frequencies(&self) -> Result<Vec<Frequency>>;
+    fn select_transfers(&self) -> Result<Vec<Transfer>>;
+    fn select_fare_attributes(&self) -> Result<Vec<FareAttribute>>;
+    fn select_fare_rules(&self) -> Result<Vec<FareRule>>;
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::external::gtfs::agency::AgencyId;
+use crate::external::gtfs::Currency;
+use crate::external::gtfscsv::GTFSFile;
+use crate::external::gtfsdb::Table;
+
+/// 運賃ID (ex: F_1)
+pub type FareId = String;
+
+/// 運賃情報
+/// https://developers.google.com/transit/gtfs/reference?hl=ja#fare_attributestxt
+/// https://www.gtfs.jp/developpers-guide/format-reference.html#fare_attributes
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone, Hash)]
+pub struct FareAttribute {
+    /// 運賃ID
+    pub fare_id: FareId,
+    /// 運賃 (ex: 210)
+    pub price: String,
+    /// 通貨 (ex: JPY)
+    pub currency_type: Currency,
+    /// 支払いタイミング (0:乗車後, 1:乗車前)
+    pub payment_method: i32,
+    /// 乗換回数 (空:無制限, 0:不可, 1:1回, 2:2回)
+    pub transfers: Option<i32>,
+    /// 事業者ID
+    pub agency_id: Option<AgencyId>,
+    /// 乗換有効期限 (秒)
+    pub transfer_duration: Option<i32>,
+}
+
+impl GTFSFile for FareAttribute {
+    fn file_name() -> &'static str {
+        "fare_attributes.txt"
+    }
+}
+
+impl Table for FareAttribute {
+    fn table_name() -> &'static str {
+        "fare_attributes"
+    }
+
+    fn column_names() -> &'static [&'static str] {
+        &[
+            "fare_id",
+            "price",
+            "currency_type",
+            "payment_method",
+            "transfers",
+            "agency_id",
+            "transfer_duration",
+        ]
+    }
+
+    fn create_sql() -> &'static str {
+        "
+        fare_id text primary key,
+        price text not null,
+        currency_type text not null,
+        payment_method int not null,
+        transfers int,
+        agency_id text,
+        transfer_duration int
+        "
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::external::gtfs::trips::TripId;
+use crate::external::gtfscsv::GTFSFile;
+use crate::external::gtfsdb::Table;
+
+/// 運行間隔情報
+/// https://developers.google.com/transit/gtfs/reference?hl=ja#frequenciestxt
+/// https://www.gtfs.jp/developpers-guide/format-reference.html#frequencies
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone, Hash)]
+pub struct Frequency {
+    /// 便ID
+    pub trip_id: TripId,
+    /// 開始時刻 (ex: 7:00:00)
+    pub start_time: String,
+    /// 終了時刻 (ex: 10:00:00)
+    pub end_time: String,
+    /// 運行間隔 (秒)
+    pub headway_secs: i32,
+    /// 運行間隔タイプ (0:始発基準, 1:等間隔)
+    pub exact_times: Option<i32>,
+}
+
+impl GTFSFile for Frequency {
+    fn file_name() -> &'static str {
+        "frequencies.txt"
+    }
+}
+
+impl Table for Frequency {
+    fn table_name() -> &'static str {
+        "frequencies"
+    }
+
+    fn column_names() -> &'static [&'static str] {
+        &[
+            "trip_id",
+            "start_time",
+            "end_time",
+            "headway_secs",
+            "exact_times",
+        ]
+    }
+
+    fn create_sql() -> &'static str {
+        "
+        trip_id text not null,
+        start_time text not null,
+        end_time text not null,
+        headway_secs int not null,
+        exact_times int
+        "
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::external::gtfscsv::GTFSFile;
+use crate::external::gtfsdb::Table;
+
+/// 描画ID (ex: S_1)
+pub type ShapeId = String;
+
+/// 描画情報
+/// https://developers.google.com/transit/gtfs/reference?hl=ja#shapestxt
+/// https://www.gtfs.jp/developpers-guide/format-reference.html#shapes
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct Shape {
+    /// 描画ID
+    pub shape_id: ShapeId,
+    /// 描画緯度 (ex: 35.681236)
+    pub shape_pt_lat: f64,
+    /// 描画経度 (ex: 139.767125)
+    pub shape_pt_lon: f64,
+    /// 描画順序
+    pub shape_pt_sequence: i32,
+    /// 描画距離 (m)
+    pub shape_dist_traveled: Option<f64>,
+}
+
+impl GTFSFile for Shape {
+    fn file_name() -> &'static str {
+        "shapes.txt"
+    }
+}
+
+impl Table for Shape {
+    fn table_name() -> &'static str {
+        "shapes"
+    }
+
+    fn column_names() -> &'static [&'static str] {
+        &[
+            "shape_id",
+            "shape_pt_lat",
+            "shape_pt_lon",
+            "shape_pt_sequence",
+            "shape_dist_traveled",
+        ]
+    }
+
+    fn create_sql() -> &'static str {
+        "
+        shape_id text not null,
+        shape_pt_lat real not null,
+        shape_pt_lon real not null,
+        shape_pt_sequence int not null,
+        shape_dist_traveled real
+        "
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::external::gtfs::stops::StopId;
+use crate::external::gtfs::trips::TripId;
+use crate::external::gtfscsv::GTFSFile;
+use crate::external::gtfsdb::Table;
+
+/// 通過時刻情報
+/// https://developers.google.com/transit/gtfs/reference?hl=ja#stop_timestxt
+/// https://www.gtfs.jp/developpers-guide/format-reference.html#stop_times
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone, Hash)]
+pub struct StopTime {
+    /// 便ID
+    pub trip_id: TripId,
+    /// 到着時刻 (ex: 7:00:00, 25:00:00)
+    pub arrival_time: Option<String>,
+    /// 出発時刻 (ex: 7:00:00, 25:00:00)
+    pub departure_time: Option<String>,
+    /// 標柱ID
+    pub stop_id: StopId,
+    /// 通過順位
+    pub stop_sequence: i32,
+    /// 停留所行先 (ex: 東京駅前)
+    pub stop_headsign: Option<String>,
+    /// 乗車区分 (0:通常, 1:乗車不可, 2:要電話予約, 3:要運転手連絡)
+    pub pickup_type: Option<i32>,
+    /// 降車区分 (0:通常, 1:降車不可, 2:要電話予約, 3:要運転手連絡)
+    pub drop_off_type: Option<i32>,
+    /// 通過距離 (m)
+    pub shape_dist_traveled: Option<f64>,
+    /// 発着時刻正確性 (0:概定時刻, 1:正確な時刻)
+    pub timepoint: Option<i32>,
+}
+
+impl GTFSFile for StopTime {
+    fn file_name() -> &'static str {
+        "stop_times.txt"
+    }
+}
+
+impl Table for StopTime {
+    fn table_name() -> &'static str {
+        "stop_times"
+    }
+
+    fn column_names() -> &'static [&'static str] {
+        &[
+            "trip_id",
+            "arrival_time",
+            "departure_time",
+            "stop_id",
+            "stop_sequence",
+            "stop_headsign",
+            "pickup_type",
+            "drop_off_type",
+            "shape_dist_traveled",
+            "timepoint",
+        ]
+    }
+
+    fn create_sql() -> &'static str {
+        "
+        trip_id text not null,
+        arrival_time text,
+        departure_time text,
+        stop_id text not null,
+        stop_sequence int not null,
+        stop_headsign text,
+        pickup_type int,
+        drop_off_type int,
+        shape_dist_traveled real,
+        timepoint int
+        "
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::external::gtfs::stops::StopId;
+use crate::external::gtfscsv::GTFSFile;
+use crate::external::gtfsdb::Table;
+
+/// 乗換情報
+/// https://developers.google.com/transit/gtfs/reference?hl=ja#transferstxt
+/// https://www.gtfs.jp/developpers-guide/format-reference.html#transfers
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone, Hash)]
+pub struct Transfer {
+    /// 乗継元標柱ID
+    pub from_stop_id: StopId,
+    /// 乗継先標柱ID
+    pub to_stop_id: StopId,
+    /// 乗継タイプ (0:推奨, 1:同一時刻, 2:最低乗継時間, 3:乗継不可)
+    pub transfer_type: i32,
+    /// 乗継最低時間 (秒)
+    pub min_transfer_time: Option<i32>,
+}
+
+impl GTFSFile for Transfer {
+    fn file_name() -> &'static str {
+        "transfers.txt"
+    }
+}
+
+impl Table for Transfer {
+    fn table_name() -> &'static str {
+        "transfers"
+    }
+
+    fn column_names() -> &'static [&'static str] {
+        &[
+            "from_stop_id",
+            "to_stop_id",
+            "transfer_type",
+            "min_transfer_time",
+        ]
+    }
+
+    fn create_sql() -> &'static str {
+        "
+        from_stop_id text not null,
+        to_stop_id text not null,
+        transfer_type int not null,
+        min_transfer_time int
+        "
+    }
+}
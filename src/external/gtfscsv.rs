@@ -0,0 +1,167 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// GTFSのファイルを表現する
+pub trait GTFSFile {
+    /// GTFS仕様上のファイル名 (ex: agency.txt)
+    fn file_name() -> &'static str;
+}
+
+/// 取りこみ対象とする既知のGTFSファイル名 (`GTFSFile::file_name()`に対応)
+///
+/// zipからはこの集合のメンバーだけをベース名で取り出す。任意のパスを
+/// ベース名で展開すると、ネストしたファイル同士が衝突しうるため。
+const GTFS_FILE_NAMES: &[&str] = &[
+    "agency.txt",
+    "stops.txt",
+    "routes.txt",
+    "trips.txt",
+    "stop_times.txt",
+    "calendar.txt",
+    "calendar_dates.txt",
+    "shapes.txt",
+    "frequencies.txt",
+    "transfers.txt",
+    "fare_attributes.txt",
+    "fare_rules.txt",
+];
+
+/// GTFSフィードの入力元
+///
+/// `gtfs-structures` と同様に、展開済みディレクトリ・zipアーカイブ・
+/// リモートURLのいずれからでも読みこめるようにする。
+#[derive(Debug, Clone)]
+pub enum Source {
+    /// 展開済みの`.txt`が並ぶディレクトリ
+    Dir(PathBuf),
+    /// GTFSフィードのzipアーカイブ
+    Zip(PathBuf),
+    /// 公開されているGTFSフィードのURL (http/https)
+    Url(String),
+}
+
+impl Source {
+    /// パス文字列から入力元を推定する
+    ///
+    /// `http(s)://`で始まればURL、拡張子が`.zip`ならzip、それ以外は
+    /// ディレクトリとして扱う。
+    pub fn from_arg(arg: &Path) -> Self {
+        let s = arg.to_string_lossy();
+        if s.starts_with("http://") || s.starts_with("https://") {
+            Source::Url(s.into_owned())
+        } else if arg.extension().map_or(false, |e| e == "zip") {
+            Source::Zip(arg.to_path_buf())
+        } else {
+            Source::Dir(arg.to_path_buf())
+        }
+    }
+}
+
+/// CSV(GTFS)フィードの入力元となるディレクトリを保持する
+///
+/// zip・URLの入力は一時ディレクトリへ展開し、展開済みディレクトリと同じ
+/// 経路 (`io::read(&dir.join(..))`) で各テーブルを読めるようにする。
+pub struct GtfsCsv {
+    dir: PathBuf,
+    /// zip/URLを展開した一時ディレクトリ。Drop時に削除されるため保持する。
+    _tmp: Option<tempfile::TempDir>,
+}
+
+impl GtfsCsv {
+    pub fn new(source: &Source) -> Result<Self> {
+        match source {
+            Source::Dir(dir) => Ok(Self {
+                dir: dir.clone(),
+                _tmp: None,
+            }),
+            Source::Zip(path) => Self::from_zip(path),
+            Source::Url(url) => {
+                // ダウンロードした一時ファイルはこの関数を抜けると削除される
+                let archive = download_to_temp(url)?;
+                Self::from_zip(archive.path())
+            }
+        }
+    }
+
+    /// zipアーカイブを一時ディレクトリへ展開し、そのディレクトリを入力元とする
+    fn from_zip(path: &Path) -> Result<Self> {
+        let tmp = tempfile::tempdir()?;
+        extract_zip_members(path, tmp.path())?;
+        Ok(Self {
+            dir: tmp.path().to_path_buf(),
+            _tmp: Some(tmp),
+        })
+    }
+
+    /// GTFSファイルが配置されたディレクトリのパス
+    ///
+    /// 入力がディレクトリならそのまま、zip/URLなら展開先の一時ディレクトリを返す。
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// zipアーカイブから既知のGTFSファイルだけを`out_dir`へ展開する
+fn extract_zip_members(path: &Path, out_dir: &Path) -> Result<()> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("zipを開けませんでした: {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if !entry.is_file() {
+            continue;
+        }
+        // ネストの有無によらず、既知のGTFSファイル名のメンバーだけを取り出す
+        let base = Path::new(entry.name())
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned());
+        let Some(name) = base.filter(|n| GTFS_FILE_NAMES.contains(&n.as_str())) else {
+            continue;
+        };
+        let mut out = std::fs::File::create(out_dir.join(name))?;
+        std::io::copy(&mut entry, &mut out)?;
+    }
+    Ok(())
+}
+
+/// URLのGTFSフィードを一時ファイルへダウンロードする
+///
+/// 返した[`tempfile::NamedTempFile`]が生きている間だけファイルは存在し、
+/// Dropされると削除される。呼び出し側は展開が終わるまで保持すること。
+fn download_to_temp(url: &str) -> Result<tempfile::NamedTempFile> {
+    let bytes = reqwest::blocking::get(url)
+        .with_context(|| format!("ダウンロードに失敗しました: {}", url))?
+        .error_for_status()?
+        .bytes()?;
+    let mut tmp = tempfile::NamedTempFile::new()?;
+    tmp.write_all(&bytes)?;
+    tmp.flush()?;
+    Ok(tmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_arg_detects_url() {
+        let s = Source::from_arg(Path::new("https://example.com/feed.zip"));
+        assert!(matches!(s, Source::Url(u) if u == "https://example.com/feed.zip"));
+        let s = Source::from_arg(Path::new("http://example.com/feed"));
+        assert!(matches!(s, Source::Url(_)));
+    }
+
+    #[test]
+    fn from_arg_detects_zip_by_extension() {
+        let s = Source::from_arg(Path::new("/tmp/feed.zip"));
+        assert!(matches!(s, Source::Zip(p) if p == PathBuf::from("/tmp/feed.zip")));
+    }
+
+    #[test]
+    fn from_arg_defaults_to_dir() {
+        let s = Source::from_arg(Path::new("/tmp/feed"));
+        assert!(matches!(s, Source::Dir(p) if p == PathBuf::from("/tmp/feed")));
+    }
+}
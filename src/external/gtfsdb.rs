@@ -0,0 +1,255 @@
+use std::path::Path;
+
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::external::gtfs::agency::Agency;
+use crate::external::gtfs::calendar::Calendar;
+use crate::external::gtfs::calendar_dates::CalendarDate;
+use crate::external::gtfs::fare_attributes::FareAttribute;
+use crate::external::gtfs::fare_rules::FareRule;
+use crate::external::gtfs::frequencies::Frequency;
+use crate::external::gtfs::routes::Route;
+use crate::external::gtfs::shapes::Shape;
+use crate::external::gtfs::stop_times::StopTime;
+use crate::external::gtfs::stops::Stop;
+use crate::external::gtfs::transfers::Transfer;
+use crate::external::gtfs::trips::Trip;
+use crate::external::gtfs::Gtfs;
+
+/// SQLiteの1テーブルとして永続化できることを表すトレイト
+pub trait Table {
+    /// テーブル名 (ex: agency)
+    fn table_name() -> &'static str;
+    /// カラム名の並び。CSVヘッダと挿入・取得の順序を兼ねる。
+    fn column_names() -> &'static [&'static str];
+    /// `CREATE TABLE`のカラム定義部
+    fn create_sql() -> &'static str;
+}
+
+/// stop_timesのように巨大なテーブルを挿入する際、1トランザクションへまとめるチャンクの行数
+const INSERT_CHUNK_SIZE: usize = 10_000;
+
+/// SQLiteを永続化層とする[`Gtfs`]実装
+pub struct GtfsDb {
+    conn: Connection,
+}
+
+impl GtfsDb {
+    pub fn new(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        Ok(Self { conn })
+    }
+
+    fn create_table<T: Table>(&self) -> Result<()> {
+        self.conn.execute_batch(&format!(
+            "create table if not exists {} ({});",
+            T::table_name(),
+            T::create_sql().trim()
+        ))?;
+        Ok(())
+    }
+
+    fn drop_table<T: Table>(&self) -> Result<()> {
+        self.conn
+            .execute_batch(&format!("drop table if exists {};", T::table_name()))?;
+        Ok(())
+    }
+
+    /// `INSERT INTO table (cols) VALUES (:cols)`のSQLを組み立てる
+    fn insert_sql<T: Table>() -> String {
+        let cols = T::column_names();
+        let placeholders = cols
+            .iter()
+            .map(|c| format!(":{}", c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "insert into {} ({}) values ({})",
+            T::table_name(),
+            cols.join(", "),
+            placeholders
+        )
+    }
+
+    /// 1テーブル分を1トランザクションで挿入する
+    fn insert_all<T>(&mut self, records: &[T]) -> Result<()>
+    where
+        T: Table + Serialize,
+    {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let sql = Self::insert_sql::<T>();
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(&sql)?;
+            for record in records {
+                let params = serde_rusqlite::to_params_named(record)?;
+                stmt.execute(params.to_slice().as_slice())?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn select_all<T>(&self) -> Result<Vec<T>>
+    where
+        T: Table + DeserializeOwned,
+    {
+        let sql = format!(
+            "select {} from {}",
+            T::column_names().join(", "),
+            T::table_name()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = serde_rusqlite::from_rows::<T>(stmt.query([])?);
+        rows.map(|r| r.map_err(Into::into)).collect()
+    }
+}
+
+impl Gtfs for GtfsDb {
+    fn create_all(&self) -> Result<()> {
+        self.create_table::<Agency>()?;
+        self.create_table::<Stop>()?;
+        self.create_table::<Route>()?;
+        self.create_table::<Trip>()?;
+        self.create_table::<StopTime>()?;
+        self.create_table::<Calendar>()?;
+        self.create_table::<CalendarDate>()?;
+        self.create_table::<Shape>()?;
+        self.create_table::<Frequency>()?;
+        self.create_table::<Transfer>()?;
+        self.create_table::<FareAttribute>()?;
+        self.create_table::<FareRule>()?;
+        Ok(())
+    }
+
+    fn drop_all(&self) -> Result<()> {
+        self.drop_table::<Agency>()?;
+        self.drop_table::<Stop>()?;
+        self.drop_table::<Route>()?;
+        self.drop_table::<Trip>()?;
+        self.drop_table::<StopTime>()?;
+        self.drop_table::<Calendar>()?;
+        self.drop_table::<CalendarDate>()?;
+        self.drop_table::<Shape>()?;
+        self.drop_table::<Frequency>()?;
+        self.drop_table::<Transfer>()?;
+        self.drop_table::<FareAttribute>()?;
+        self.drop_table::<FareRule>()?;
+        Ok(())
+    }
+
+    fn insert_agencies(&mut self, agencies: &[Agency]) -> Result<()> {
+        self.insert_all(agencies)
+    }
+
+    fn insert_stops(&mut self, stops: &[Stop]) -> Result<()> {
+        self.insert_all(stops)
+    }
+
+    fn insert_routes(&mut self, routes: &[Route]) -> Result<()> {
+        self.insert_all(routes)
+    }
+
+    fn insert_trips(&mut self, trips: &[Trip]) -> Result<()> {
+        self.insert_all(trips)
+    }
+
+    /// stop_timesは数百万行になりうるため、チャンクごとにトランザクションを
+    /// 張って挿入し、1トランザクションが肥大化しないようにする。
+    fn insert_stop_times(&mut self, stop_times: &[StopTime]) -> Result<()> {
+        let sql = Self::insert_sql::<StopTime>();
+        for chunk in stop_times.chunks(INSERT_CHUNK_SIZE) {
+            let tx = self.conn.transaction()?;
+            {
+                let mut stmt = tx.prepare(&sql)?;
+                for stop_time in chunk {
+                    let params = serde_rusqlite::to_params_named(stop_time)?;
+                    stmt.execute(params.to_slice().as_slice())?;
+                }
+            }
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    fn insert_calendars(&mut self, calendars: &[Calendar]) -> Result<()> {
+        self.insert_all(calendars)
+    }
+
+    fn insert_calendar_dates(&mut self, calendar_dates: &[CalendarDate]) -> Result<()> {
+        self.insert_all(calendar_dates)
+    }
+
+    fn insert_shapes(&mut self, shapes: &[Shape]) -> Result<()> {
+        self.insert_all(shapes)
+    }
+
+    fn insert_frequencies(&mut self, frequencies: &[Frequency]) -> Result<()> {
+        self.insert_all(frequencies)
+    }
+
+    fn insert_transfers(&mut self, transfers: &[Transfer]) -> Result<()> {
+        self.insert_all(transfers)
+    }
+
+    fn insert_fare_attributes(&mut self, fare_attributes: &[FareAttribute]) -> Result<()> {
+        self.insert_all(fare_attributes)
+    }
+
+    fn insert_fare_rules(&mut self, fare_rules: &[FareRule]) -> Result<()> {
+        self.insert_all(fare_rules)
+    }
+
+    fn select_agencies(&self) -> Result<Vec<Agency>> {
+        self.select_all()
+    }
+
+    fn select_stops(&self) -> Result<Vec<Stop>> {
+        self.select_all()
+    }
+
+    fn select_routes(&self) -> Result<Vec<Route>> {
+        self.select_all()
+    }
+
+    fn select_trips(&self) -> Result<Vec<Trip>> {
+        self.select_all()
+    }
+
+    fn select_stop_times(&self) -> Result<Vec<StopTime>> {
+        self.select_all()
+    }
+
+    fn select_calendars(&self) -> Result<Vec<Calendar>> {
+        self.select_all()
+    }
+
+    fn select_calendar_dates(&self) -> Result<Vec<CalendarDate>> {
+        self.select_all()
+    }
+
+    fn select_shapes(&self) -> Result<Vec<Shape>> {
+        self.select_all()
+    }
+
+    fn select_frequencies(&self) -> Result<Vec<Frequency>> {
+        self.select_all()
+    }
+
+    fn select_transfers(&self) -> Result<Vec<Transfer>> {
+        self.select_all()
+    }
+
+    fn select_fare_attributes(&self) -> Result<Vec<FareAttribute>> {
+        self.select_all()
+    }
+
+    fn select_fare_rules(&self) -> Result<Vec<FareRule>> {
+        self.select_all()
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
+
+use crate::external::gtfs::calendar::Calendar;
+use crate::external::gtfs::calendar_dates::CalendarDate;
+use crate::external::gtfs::routes::Route;
+use crate::external::gtfs::stop_times::StopTime;
+use crate::external::gtfs::stops::Stop;
+use crate::external::gtfs::trips::Trip;
+
+/// 1路線分のダイヤをiCalendar(RFC-5545)へ変換するビルダ
+///
+/// 各便について、最初と最後の通過時刻からDTSTART/DTENDを、運行日の
+/// 曜日フラグからBYDAY付きの週次RRULEを生成し、`calendar_dates`の
+/// 運休(exception_type=2)はEXDATEへ、追加運行(exception_type=1)は
+/// 単発のVEVENTとして書き出す。`calendar`に該当行がない便、および運行曜日が
+/// 1つもない便は、追加運行を含めて何も出力しない。
+pub struct RouteCalendar<'a> {
+    route: &'a Route,
+    trips: &'a [Trip],
+    stop_times: &'a [StopTime],
+    stops: &'a [Stop],
+    calendars: &'a [Calendar],
+    calendar_dates: &'a [CalendarDate],
+}
+
+impl<'a> RouteCalendar<'a> {
+    pub fn new(
+        route: &'a Route,
+        trips: &'a [Trip],
+        stop_times: &'a [StopTime],
+        stops: &'a [Stop],
+        calendars: &'a [Calendar],
+        calendar_dates: &'a [CalendarDate],
+    ) -> Self {
+        Self {
+            route,
+            trips,
+            stop_times,
+            stops,
+            calendars,
+            calendar_dates,
+        }
+    }
+
+    /// 単一路線のVCALENDAR文字列を生成する
+    pub fn to_ics(&self) -> Result<String> {
+        let dtstamp = dtstamp();
+        let mut out = String::new();
+        out.push_str("BEGIN:VCALENDAR\r\n");
+        out.push_str("VERSION:2.0\r\n");
+        out.push_str("PRODID:-//hibou//GTFS//JP\r\n");
+        self.push_vevents(&mut out, &dtstamp)?;
+        out.push_str("END:VCALENDAR\r\n");
+        Ok(out)
+    }
+
+    /// 路線の全便ぶんのVEVENTを`out`へ追記する (VCALENDARの枠は付けない)
+    fn push_vevents(&self, out: &mut String, dtstamp: &str) -> Result<()> {
+        let stop_names: HashMap<&str, &str> = self
+            .stops
+            .iter()
+            .map(|s| (s.stop_id.as_str(), s.stop_name.as_str()))
+            .collect();
+        let calendars: HashMap<&str, &Calendar> = self
+            .calendars
+            .iter()
+            .map(|c| (c.service_id.as_str(), c))
+            .collect();
+
+        let summary = self
+            .route
+            .route_short_name
+            .clone()
+            .or_else(|| self.route.route_long_name.clone())
+            .unwrap_or_default();
+
+        for trip in self.trips.iter().filter(|t| t.route_id == self.route.route_id) {
+            let mut times: Vec<&StopTime> = self
+                .stop_times
+                .iter()
+                .filter(|st| st.trip_id == trip.trip_id)
+                .collect();
+            times.sort_by_key(|st| st.stop_sequence);
+            let (Some(first), Some(last)) = (times.first(), times.last()) else {
+                continue;
+            };
+
+            let calendar = match calendars.get(trip.service_id.as_str()) {
+                Some(c) => c,
+                None => continue,
+            };
+            let start_date = parse_date(&calendar.start_date)?;
+            let end_date = parse_date(&calendar.end_date)?;
+            let byday = weekdays(calendar);
+            if byday.is_empty() {
+                continue;
+            }
+
+            let dep = first
+                .departure_time
+                .as_ref()
+                .or(first.arrival_time.as_ref())
+                .context("出発時刻がありません")?;
+            let arr = last
+                .arrival_time
+                .as_ref()
+                .or(last.departure_time.as_ref())
+                .context("到着時刻がありません")?;
+
+            // 最初に運行される曜日を起点にDTSTARTを決める
+            let first_service = first_service_date(start_date, calendar);
+            let Some(base) = first_service else { continue };
+
+            let origin = stop_names.get(first.stop_id.as_str()).copied().unwrap_or("");
+            let destination = stop_names.get(last.stop_id.as_str()).copied().unwrap_or("");
+
+            out.push_str("BEGIN:VEVENT\r\n");
+            out.push_str(&format!("UID:{}@hibou\r\n", trip.trip_id));
+            out.push_str(&format!("DTSTAMP:{}\r\n", dtstamp));
+            out.push_str(&format!("SUMMARY:{}\r\n", escape(&summary)));
+            out.push_str(&format!(
+                "LOCATION:{} - {}\r\n",
+                escape(origin),
+                escape(destination)
+            ));
+            out.push_str(&format!("DTSTART:{}\r\n", datetime(base, dep)?));
+            out.push_str(&format!("DTEND:{}\r\n", datetime(base, arr)?));
+            // UNTILはDTSTART(フローティングのDATE-TIME)と値型を揃える
+            out.push_str(&format!(
+                "RRULE:FREQ=WEEKLY;BYDAY={};UNTIL={}\r\n",
+                byday.join(","),
+                until_stamp(end_date),
+            ));
+
+            let exdates: Vec<String> = self
+                .calendar_dates
+                .iter()
+                .filter(|cd| cd.service_id == trip.service_id && cd.exception_type == 2)
+                .filter_map(|cd| parse_date(&cd.date).ok())
+                .map(|d| datetime(d, dep))
+                .collect::<Result<Vec<_>>>()?;
+            if !exdates.is_empty() {
+                out.push_str(&format!("EXDATE:{}\r\n", exdates.join(",")));
+            }
+            out.push_str("END:VEVENT\r\n");
+
+            // 追加運行(exception_type=1)は単発のVEVENTとして出力する
+            for cd in self
+                .calendar_dates
+                .iter()
+                .filter(|cd| cd.service_id == trip.service_id && cd.exception_type == 1)
+            {
+                let date = parse_date(&cd.date)?;
+                out.push_str("BEGIN:VEVENT\r\n");
+                out.push_str(&format!("UID:{}-{}@hibou\r\n", trip.trip_id, cd.date));
+                out.push_str(&format!("DTSTAMP:{}\r\n", dtstamp));
+                out.push_str(&format!("SUMMARY:{}\r\n", escape(&summary)));
+                out.push_str(&format!(
+                    "LOCATION:{} - {}\r\n",
+                    escape(origin),
+                    escape(destination)
+                ));
+                out.push_str(&format!("DTSTART:{}\r\n", datetime(date, dep)?));
+                out.push_str(&format!("DTEND:{}\r\n", datetime(date, arr)?));
+                out.push_str("END:VEVENT\r\n");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 複数路線のVEVENTを1つのVCALENDARにまとめて出力する
+///
+/// カレンダーアプリの多くは1ファイル中の最初のVCALENDARしか取りこまないため、
+/// 路線ごとにVCALENDARを分けず、全路線を単一のVCALENDARへ束ねる。
+pub fn to_combined_ics(calendars: &[RouteCalendar]) -> Result<String> {
+    let dtstamp = dtstamp();
+    let mut out = String::new();
+    out.push_str("BEGIN:VCALENDAR\r\n");
+    out.push_str("VERSION:2.0\r\n");
+    out.push_str("PRODID:-//hibou//GTFS//JP\r\n");
+    for calendar in calendars {
+        calendar.push_vevents(&mut out, &dtstamp)?;
+    }
+    out.push_str("END:VCALENDAR\r\n");
+    Ok(out)
+}
+
+/// GTFS日付(YYYYMMDD)をパースする
+fn parse_date(s: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y%m%d").with_context(|| format!("不正な日付: {}", s))
+}
+
+/// calendarの曜日フラグからRRULEのBYDAY値を組み立てる
+fn weekdays(c: &Calendar) -> Vec<&'static str> {
+    let mut days = Vec::new();
+    if c.monday == 1 {
+        days.push("MO");
+    }
+    if c.tuesday == 1 {
+        days.push("TU");
+    }
+    if c.wednesday == 1 {
+        days.push("WE");
+    }
+    if c.thursday == 1 {
+        days.push("TH");
+    }
+    if c.friday == 1 {
+        days.push("FR");
+    }
+    if c.saturday == 1 {
+        days.push("SA");
+    }
+    if c.sunday == 1 {
+        days.push("SU");
+    }
+    days
+}
+
+/// start_date以降で最初に運行される日付を求める
+fn first_service_date(start: NaiveDate, c: &Calendar) -> Option<NaiveDate> {
+    (0..7).map(|i| start + Duration::days(i)).find(|d| runs_on(c, d.weekday()))
+}
+
+fn runs_on(c: &Calendar, day: Weekday) -> bool {
+    let flag = match day {
+        Weekday::Mon => c.monday,
+        Weekday::Tue => c.tuesday,
+        Weekday::Wed => c.wednesday,
+        Weekday::Thu => c.thursday,
+        Weekday::Fri => c.friday,
+        Weekday::Sat => c.saturday,
+        Weekday::Sun => c.sunday,
+    };
+    flag == 1
+}
+
+/// 24時以降の時刻表記を吸収しつつ、日付と時刻からICSのDATE-TIMEを作る
+fn datetime(date: NaiveDate, time: &str) -> Result<String> {
+    let mut parts = time.split(':');
+    let h: i64 = parts.next().context("時刻が空です")?.parse()?;
+    let m: u32 = parts.next().unwrap_or("0").parse()?;
+    let s: u32 = parts.next().unwrap_or("0").parse()?;
+    let day = date + Duration::days(h / 24);
+    Ok(format!(
+        "{}T{:02}{:02}{:02}",
+        date_stamp(day),
+        (h % 24),
+        m,
+        s
+    ))
+}
+
+fn date_stamp(date: NaiveDate) -> String {
+    format!("{:04}{:02}{:02}", date.year(), date.month(), date.day())
+}
+
+/// 現在時刻をUTCのDATE-TIME(`...Z`)として返す (DTSTAMP用)
+fn dtstamp() -> String {
+    let now = Utc::now();
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        now.year(),
+        now.month(),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+    )
+}
+
+/// RRULEのUNTIL値。DTSTART(フローティングのDATE-TIME)と値型を揃え、
+/// 終了日の23:59:59をフローティングのDATE-TIMEで表す(末尾に`Z`は付けない)。
+fn until_stamp(end_date: NaiveDate) -> String {
+    format!("{}T235959", date_stamp(end_date))
+}
+
+/// ICSテキスト値の特殊文字をエスケープする
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar(flags: [i32; 7]) -> Calendar {
+        Calendar {
+            service_id: "weekday".to_owned(),
+            monday: flags[0],
+            tuesday: flags[1],
+            wednesday: flags[2],
+            thursday: flags[3],
+            friday: flags[4],
+            saturday: flags[5],
+            sunday: flags[6],
+            start_date: "20260101".to_owned(),
+            end_date: "20261231".to_owned(),
+        }
+    }
+
+    #[test]
+    fn datetime_keeps_same_day_for_normal_time() {
+        let date = NaiveDate::from_ymd_opt(2026, 7, 25).unwrap();
+        assert_eq!(datetime(date, "7:05:00").unwrap(), "20260725T070500");
+    }
+
+    #[test]
+    fn datetime_rolls_over_past_midnight() {
+        // 24:00:00以降は翌日へ繰り上げ、時刻は24で割った余りにする
+        let date = NaiveDate::from_ymd_opt(2026, 7, 25).unwrap();
+        assert_eq!(datetime(date, "24:00:00").unwrap(), "20260726T000000");
+        assert_eq!(datetime(date, "25:30:15").unwrap(), "20260726T013015");
+    }
+
+    #[test]
+    fn until_stamp_is_floating_datetime() {
+        // DTSTARTがフローティングなのでUNTILも`Z`なしのDATE-TIMEにする
+        let end = NaiveDate::from_ymd_opt(2026, 12, 31).unwrap();
+        assert_eq!(until_stamp(end), "20261231T235959");
+    }
+
+    #[test]
+    fn weekdays_maps_flags_to_byday() {
+        assert_eq!(
+            weekdays(&calendar([1, 1, 1, 1, 1, 0, 0])),
+            vec!["MO", "TU", "WE", "TH", "FR"]
+        );
+        assert_eq!(weekdays(&calendar([0, 0, 0, 0, 0, 1, 1])), vec!["SA", "SU"]);
+        assert!(weekdays(&calendar([0, 0, 0, 0, 0, 0, 0])).is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use strum::{EnumString, EnumVariantNames};
+
+use crate::external::gtfscsv::GTFSFile;
+
+/// 出力フォーマット
+#[derive(Debug, Clone, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum Format {
+    /// カンマ区切り (RFC-4180)
+    Csv,
+    /// JSON
+    Json,
+    /// iCalendar (RFC-5545)
+    Ics,
+}
+
+/// GTFSファイルを読みこんでデシリアライズする
+pub fn read<T>(path: &Path) -> Result<Vec<T>>
+where
+    T: GTFSFile + DeserializeOwned,
+{
+    let file = std::fs::File::open(path)?;
+    read_reader::<T, _>(file)
+}
+
+/// 任意項目のGTFSファイルを読みこむ。存在しなければ空のVecを返す。
+///
+/// shapes/frequencies/transfers/fare_*などは必須ではなく、小規模なフィードでは
+/// 欠けていることが多い。欠落で`db create`を失敗させないために用いる。
+pub fn read_optional<T>(path: &Path) -> Result<Vec<T>>
+where
+    T: GTFSFile + DeserializeOwned,
+{
+    if path.exists() {
+        read::<T>(path)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// 任意のリーダからGTFSファイルを読みこんでデシリアライズする
+pub fn read_reader<T, R>(reader: R) -> Result<Vec<T>>
+where
+    T: GTFSFile + DeserializeOwned,
+    R: std::io::Read,
+{
+    let mut rdr = csv::ReaderBuilder::new().from_reader(reader);
+    let records = rdr.deserialize().collect::<Result<Vec<T>, _>>()?;
+    Ok(records)
+}
+
+/// 選択したフォーマットで標準出力へ書き出す
+pub fn write<T>(records: &[T], format: &Format) -> Result<()>
+where
+    T: Serialize,
+{
+    match format {
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for record in records {
+                wtr.serialize(record)?;
+            }
+            wtr.flush()?;
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), records)?;
+        }
+        Format::Ics => {
+            anyhow::bail!("icsは専用の出力経路を利用してください");
+        }
+    }
+    Ok(())
+}
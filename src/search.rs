@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, INDEXED, STORED,
+};
+use tantivy::tokenizer::{NgramTokenizer, TextAnalyzer};
+use tantivy::{doc, Index, IndexWriter};
+
+use crate::external::gtfs::agency::Agency;
+use crate::external::gtfs::routes::Route;
+use crate::external::gtfs::stops::Stop;
+
+/// 駅名のような日本語の断片検索に用いるトークナイザ名
+const JP_TOKENIZER: &str = "gram";
+
+/// 検索対象のエンティティ種別
+pub const ENTITY_STOP: &str = "stop";
+pub const ENTITY_ROUTE: &str = "route";
+pub const ENTITY_AGENCY: &str = "agency";
+
+/// 検索ヒット1件。GTFS上のidと種別・名称・スコアを持つ。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SearchHit {
+    /// GTFS上のエンティティid
+    pub id: String,
+    /// エンティティ種別 (stop/route/agency)
+    pub entity_type: String,
+    /// 一致した名称テキスト
+    pub name: String,
+    /// 関連度スコア
+    pub score: f32,
+}
+
+/// SQLite dbの隣に置く全文検索インデックス
+pub struct SearchIndex {
+    index: Index,
+    id: Field,
+    entity_type: Field,
+    name: Field,
+}
+
+impl SearchIndex {
+    /// dbファイルに対応するインデックスディレクトリのパス
+    pub fn dir_for(database: &Path) -> PathBuf {
+        let mut name = database.file_name().unwrap_or_default().to_os_string();
+        name.push(".index");
+        database.with_file_name(name)
+    }
+
+    fn schema() -> (Schema, Field, Field, Field) {
+        let mut builder = Schema::builder();
+        let id = builder.add_text_field("id", STORED);
+        let entity_type = builder.add_text_field("entity_type", STORED | INDEXED);
+        // 駅名などの日本語断片検索のため、nameには`default`ではなくngramトークナイザを使う
+        let name_options = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(JP_TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored();
+        let name = builder.add_text_field("name", name_options);
+        (builder.build(), id, entity_type, name)
+    }
+
+    fn register_tokenizer(index: &Index) {
+        let analyzer = TextAnalyzer::from(NgramTokenizer::new(1, 2, false));
+        index.tokenizers().register(JP_TOKENIZER, analyzer);
+    }
+
+    /// インデックスを新規作成する(既存のものは置き換える)
+    ///
+    /// `db create`は再実行可能なので、既存のインデックスディレクトリが
+    /// 残っていると`Index::create_in_dir`が`IndexAlreadyExists`で失敗する。
+    /// いったんディレクトリごと消してから作り直す。
+    pub fn create(dir: &Path) -> Result<Self> {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)?;
+        }
+        std::fs::create_dir_all(dir)?;
+        let (schema, id, entity_type, name) = Self::schema();
+        let index = Index::create_in_dir(dir, schema)?;
+        Self::register_tokenizer(&index);
+        Ok(Self {
+            index,
+            id,
+            entity_type,
+            name,
+        })
+    }
+
+    /// 既存のインデックスを開く
+    pub fn open(dir: &Path) -> Result<Self> {
+        let index = Index::open_in_dir(dir)?;
+        Self::register_tokenizer(&index);
+        let schema = index.schema();
+        Ok(Self {
+            id: schema.get_field("id").unwrap(),
+            entity_type: schema.get_field("entity_type").unwrap(),
+            name: schema.get_field("name").unwrap(),
+            index,
+        })
+    }
+
+    /// 停留所・経路・事業者の名称をインデックスへ登録する
+    pub fn index_entities(
+        &self,
+        agencies: &[Agency],
+        stops: &[Stop],
+        routes: &[Route],
+    ) -> Result<()> {
+        let mut writer: IndexWriter = self.index.writer(50_000_000)?;
+        for a in agencies {
+            writer.add_document(doc!(
+                self.id => a.agency_id.clone(),
+                self.entity_type => ENTITY_AGENCY,
+                self.name => a.agency_name.clone(),
+            ))?;
+        }
+        for s in stops {
+            writer.add_document(doc!(
+                self.id => s.stop_id.clone(),
+                self.entity_type => ENTITY_STOP,
+                self.name => s.stop_name.clone(),
+            ))?;
+        }
+        for r in routes {
+            let name = r
+                .route_short_name
+                .clone()
+                .or_else(|| r.route_long_name.clone())
+                .unwrap_or_default();
+            writer.add_document(doc!(
+                self.id => r.route_id.clone(),
+                self.entity_type => ENTITY_ROUTE,
+                self.name => name,
+            ))?;
+        }
+        writer.commit()?;
+        Ok(())
+    }
+
+    /// クエリを実行し、関連度順にヒットを返す
+    pub fn query(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let reader = self.index.reader()?;
+        let searcher = reader.searcher();
+        let mut parser = QueryParser::for_index(&self.index, vec![self.name]);
+        // スキーマのnameと同じngramトークナイザでクエリを分割する
+        parser.set_field_tokenizer(self.name, JP_TOKENIZER);
+        let parsed = parser.parse_query(query)?;
+        let top = searcher.search(&parsed, &TopDocs::with_limit(limit))?;
+
+        let mut hits = Vec::with_capacity(top.len());
+        for (score, addr) in top {
+            let doc = searcher.doc(addr)?;
+            hits.push(SearchHit {
+                id: text(&doc, self.id),
+                entity_type: text(&doc, self.entity_type),
+                name: text(&doc, self.name),
+                score,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+fn text(doc: &tantivy::Document, field: Field) -> String {
+    doc.get_first(field)
+        .and_then(|v| v.as_text())
+        .unwrap_or_default()
+        .to_owned()
+}
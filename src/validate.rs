@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::external::gtfs::agency::Agency;
+use crate::external::gtfs::calendar::Calendar;
+use crate::external::gtfs::calendar_dates::CalendarDate;
+use crate::external::gtfs::routes::Route;
+use crate::external::gtfs::stop_times::StopTime;
+use crate::external::gtfs::stops::Stop;
+use crate::external::gtfs::trips::Trip;
+
+/// 参照整合性違反1件
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// 違反が見つかったファイル名 (ex: trips.txt)
+    pub file: String,
+    /// ファイル内の行番号 (ヘッダを1とした1始まり)
+    pub row: usize,
+    /// 違反したルール
+    pub rule: String,
+    /// 違反の原因となったid
+    pub id: String,
+}
+
+impl Violation {
+    fn new(file: &str, row: usize, rule: &str, id: impl Into<String>) -> Self {
+        Self {
+            file: file.to_owned(),
+            row,
+            rule: rule.to_owned(),
+            id: id.into(),
+        }
+    }
+}
+
+/// クロスファイルの外部キーと必須項目を検証する
+///
+/// 行番号はヘッダを1行目として、レコードが`+2`の位置にある前提で算出する。
+pub fn validate(
+    agencies: &[Agency],
+    stops: &[Stop],
+    routes: &[Route],
+    trips: &[Trip],
+    stop_times: &[StopTime],
+    calendars: &[Calendar],
+    calendar_dates: &[CalendarDate],
+) -> Vec<Violation> {
+    let agency_ids: HashSet<&str> = agencies.iter().map(|a| a.agency_id.as_str()).collect();
+    let route_ids: HashSet<&str> = routes.iter().map(|r| r.route_id.as_str()).collect();
+    let stop_ids: HashSet<&str> = stops.iter().map(|s| s.stop_id.as_str()).collect();
+    let trip_ids: HashSet<&str> = trips.iter().map(|t| t.trip_id.as_str()).collect();
+    let service_ids: HashSet<&str> = calendars
+        .iter()
+        .map(|c| c.service_id.as_str())
+        .chain(calendar_dates.iter().map(|c| c.service_id.as_str()))
+        .collect();
+
+    let mut violations = Vec::new();
+
+    for (i, a) in agencies.iter().enumerate() {
+        if a.agency_timezone.trim().is_empty() {
+            violations.push(Violation::new(
+                "agency.txt",
+                i + 2,
+                "agency_timezone is required",
+                &a.agency_id,
+            ));
+        }
+        if !is_url(&a.agency_url) {
+            violations.push(Violation::new(
+                "agency.txt",
+                i + 2,
+                "agency_url is required and must be http(s)",
+                &a.agency_id,
+            ));
+        }
+    }
+
+    for (i, r) in routes.iter().enumerate() {
+        if let Some(agency_id) = &r.agency_id {
+            if !agency_ids.contains(agency_id.as_str()) {
+                violations.push(Violation::new(
+                    "routes.txt",
+                    i + 2,
+                    "route.agency_id must resolve to an agency",
+                    agency_id,
+                ));
+            }
+        }
+    }
+
+    for (i, t) in trips.iter().enumerate() {
+        if !route_ids.contains(t.route_id.as_str()) {
+            violations.push(Violation::new(
+                "trips.txt",
+                i + 2,
+                "trip.route_id must resolve to a route",
+                &t.route_id,
+            ));
+        }
+        if !service_ids.contains(t.service_id.as_str()) {
+            violations.push(Violation::new(
+                "trips.txt",
+                i + 2,
+                "trip.service_id must resolve to a calendar/calendar_dates entry",
+                &t.service_id,
+            ));
+        }
+    }
+
+    for (i, st) in stop_times.iter().enumerate() {
+        if !trip_ids.contains(st.trip_id.as_str()) {
+            violations.push(Violation::new(
+                "stop_times.txt",
+                i + 2,
+                "stop_time.trip_id must resolve to a trip",
+                &st.trip_id,
+            ));
+        }
+        if !stop_ids.contains(st.stop_id.as_str()) {
+            violations.push(Violation::new(
+                "stop_times.txt",
+                i + 2,
+                "stop_time.stop_id must resolve to a stop",
+                &st.stop_id,
+            ));
+        }
+    }
+
+    violations
+}
+
+fn is_url(s: &str) -> bool {
+    s.starts_with("http://") || s.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agency(id: &str, url: &str, timezone: &str) -> Agency {
+        Agency {
+            agency_id: id.to_owned(),
+            agency_name: "都営バス".to_owned(),
+            agency_url: url.to_owned(),
+            agency_timezone: timezone.to_owned(),
+            agency_lang: None,
+            agency_phone: None,
+            agency_fare_url: None,
+            agency_email: None,
+        }
+    }
+
+    fn stop_time(trip_id: &str, stop_id: &str) -> StopTime {
+        StopTime {
+            trip_id: trip_id.to_owned(),
+            arrival_time: Some("7:00:00".to_owned()),
+            departure_time: Some("7:00:00".to_owned()),
+            stop_id: stop_id.to_owned(),
+            stop_sequence: 1,
+            stop_headsign: None,
+            pickup_type: None,
+            drop_off_type: None,
+            shape_dist_traveled: None,
+            timepoint: None,
+        }
+    }
+
+    #[test]
+    fn accepts_a_well_formed_agency() {
+        let agencies = [agency("a1", "https://example.com", "Asia/Tokyo")];
+        let violations = validate(&agencies, &[], &[], &[], &[], &[], &[]);
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn flags_missing_timezone_and_bad_url() {
+        let agencies = [agency("a1", "ftp://example.com", "")];
+        let violations = validate(&agencies, &[], &[], &[], &[], &[], &[]);
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.file == "agency.txt" && v.id == "a1"));
+        assert!(violations.iter().any(|v| v.rule.contains("agency_timezone")));
+        assert!(violations.iter().any(|v| v.rule.contains("agency_url")));
+    }
+
+    #[test]
+    fn flags_stop_time_with_unresolved_trip_and_stop() {
+        let stop_times = [stop_time("t_missing", "s_missing")];
+        let violations = validate(&[], &[], &[], &[], &stop_times, &[], &[]);
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().any(|v| v.rule.contains("trip_id") && v.id == "t_missing"));
+        assert!(violations.iter().any(|v| v.rule.contains("stop_id") && v.id == "s_missing"));
+        assert!(violations.iter().all(|v| v.row == 2));
+    }
+}